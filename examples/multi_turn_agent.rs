@@ -1,93 +1,14 @@
 use std::sync::Arc;
 
+use k8s_ops::{agent::MultiTurnAgent, apply::apply_object, cluster::ClusterRegistry, discovery};
 use kube::{
-    api::{Api, ApiResource, DynamicObject, GroupVersionKind, PostParams},
-    Client,
-};
-use rig::{
-    agent::Agent,
-    completion::{self, Completion, PromptError, ToolDefinition},
-    message::{AssistantContent, Message, ToolCall, ToolFunction, ToolResultContent, UserContent},
-    providers::anthropic,
-    tool::Tool,
-    OneOrMany,
+    api::{Api, DynamicObject},
+    Client, ResourceExt,
 };
+use rig::{completion::ToolDefinition, providers::anthropic, tool::Tool};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-struct MultiTurnAgent<M: rig::completion::CompletionModel> {
-    agent: Agent<M>,
-    chat_history: Vec<completion::Message>,
-}
-
-impl<M: rig::completion::CompletionModel> MultiTurnAgent<M> {
-    async fn multi_turn_prompt(
-        &mut self,
-        prompt: impl Into<Message> + Send,
-    ) -> Result<String, PromptError> {
-        let mut current_prompt: Message = prompt.into();
-        loop {
-            println!("Current Prompt: {:?}\n", current_prompt);
-            // println!("Chat History: {:?}\n", self.chat_history);
-            let resp = self
-                .agent
-                .completion(current_prompt.clone(), self.chat_history.clone())
-                .await?
-                .send()
-                .await?;
-
-            let mut final_text = None;
-            if resp.choice.is_empty() {
-                return Ok("执行完成".to_string());
-            }
-            for content in resp.choice.into_iter() {
-                match content {
-                    AssistantContent::Text(text) => {
-                        println!("Intermediate Response: {:?}\n", text.text);
-                        final_text = Some(text.text.clone());
-                        self.chat_history.push(current_prompt.clone());
-                        let response_message = Message::Assistant {
-                            content: OneOrMany::one(AssistantContent::text(&text.text)),
-                        };
-                        self.chat_history.push(response_message);
-                    }
-                    AssistantContent::ToolCall(content) => {
-                        self.chat_history.push(current_prompt.clone());
-                        let tool_call_msg = AssistantContent::ToolCall(content.clone());
-                        println!("Tool Call Msg: {:?}\n", tool_call_msg);
-
-                        self.chat_history.push(Message::Assistant {
-                            content: OneOrMany::one(tool_call_msg),
-                        });
-
-                        let ToolCall {
-                            id,
-                            function: ToolFunction { name, arguments },
-                        } = content;
-
-                        let tool_result =
-                            self.agent.tools.call(&name, arguments.to_string()).await?;
-
-                        current_prompt = Message::User {
-                            content: OneOrMany::one(UserContent::tool_result(
-                                id,
-                                OneOrMany::one(ToolResultContent::text(tool_result)),
-                            )),
-                        };
-
-                        final_text = None;
-                        break;
-                    }
-                }
-            }
-
-            if let Some(text) = final_text {
-                return Ok(text);
-            }
-        }
-    }
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Create OpenAI client
@@ -107,10 +28,7 @@ async fn main() -> anyhow::Result<()> {
         .tool(ApplyYamlToK8s)
         .build();
 
-    let mut agent = MultiTurnAgent {
-        agent: calculator_rag,
-        chat_history: Vec::new(),
-    };
+    let mut agent = MultiTurnAgent::new(calculator_rag);
 
     // Prompt the agent and print the response
     let result = agent
@@ -138,6 +56,12 @@ pub enum ApplyError {
     SerdeYamlError(#[from] serde_yaml::Error),
     #[error("Kube error: {0}")]
     KubeError(#[from] kube::Error),
+    #[error("Discovery error: {0}")]
+    DiscoveryError(#[from] k8s_ops::discovery::DiscoveryError),
+    #[error("Cluster error: {0}")]
+    ClusterError(#[from] k8s_ops::cluster::ClusterError),
+    #[error("Apply error: {0}")]
+    CoreApplyError(#[from] k8s_ops::apply::ApplyError),
 }
 
 //生成k8s YAML 并部署资源
@@ -147,6 +71,9 @@ struct ApplyYamlToK8s;
 #[derive(Deserialize, Clone, Serialize)]
 struct K8sArg {
     user_input: String,
+    /// 目标集群名，对应 `ClusterRegistry` 里注册的 kubeconfig context 名（或
+    /// `"in-cluster"`）；缺省表示使用当前 kubeconfig context。
+    cluster: Option<String>,
 }
 impl Tool for ApplyYamlToK8s {
     const NAME: &'static str = "apply_yaml_to_k8s";
@@ -169,6 +96,11 @@ impl Tool for ApplyYamlToK8s {
                                         "type": "string",
                                         "description": "yaml文件内容"
                                     },
+                                "cluster":
+                                    {
+                                        "type": "string",
+                                        "description": "目标集群名，对应 kubeconfig context；缺省使用当前 context"
+                                    },
                             }
                     }
             }
@@ -179,7 +111,7 @@ impl Tool for ApplyYamlToK8s {
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         println!("执行K8S资源部署{}", args.user_input);
         let yaml_content = Arc::new(args.user_input);
-        let resp = self.apply_yaml_to_k8s(&yaml_content).await?;
+        let resp = self.apply_yaml_to_k8s(&yaml_content, args.cluster).await?;
         Ok(resp)
     }
 
@@ -189,63 +121,54 @@ impl Tool for ApplyYamlToK8s {
 }
 
 impl ApplyYamlToK8s {
-    async fn apply_yaml_to_k8s(&self, yaml_str: &Arc<String>) -> Result<String, ApplyError> {
+    /// 解析 `---` 分隔的多文档 YAML 清单，逐个应用到 `cluster` 指定的集群（缺省为
+    /// 当前 kubeconfig context）。单个文档失败不影响其余文档，每个文档的结果
+    /// （kind/name/namespace + 成功或错误信息）都会被收集进返回值。
+    async fn apply_yaml_to_k8s(
+        &self,
+        yaml_str: &Arc<String>,
+        cluster: Option<String>,
+    ) -> Result<String, ApplyError> {
         let yaml_str_clone = Arc::clone(yaml_str);
         let (tx, rx) = std::sync::mpsc::channel();
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
             let result = rt.block_on(async {
-                let client = Client::try_default().await?;
-                let obj: DynamicObject = serde_yaml::from_str(&yaml_str_clone)?;
-                // API版本和类型信息
-                // let api_version = obj
-                //     .types
-                //     .as_ref()
-                //     .and_then(|t| Some(t.api_version.clone()))
-                //     .ok_or_else(|| anyhow::anyhow!("API版本信息缺失"))?;
-                let api_version = obj
-                    .types
-                    .as_ref()
-                    .map(|t| t.api_version.clone())
-                    .ok_or_else(|| anyhow::anyhow!("API版本信息缺失"))?;
-
-                // let kind = obj
-                //     .types
-                //     .as_ref()
-                //     .and_then(|t| Some(t.kind.clone()))
-                //     .ok_or_else(|| anyhow::anyhow!("类型信息缺失"))?;
-
-                let kind = obj
-                    .types
-                    .as_ref()
-                    .map(|t| t.kind.clone())
-                    .ok_or_else(|| anyhow::anyhow!("类型信息缺失"))?;
-
-                // 解析 API 版本为 (group, version)
-                let (group, version) = parse_api_version(api_version.as_str());
-                let gvk = GroupVersionKind::gvk(group.unwrap(), version, kind.as_str());
-                // 获取命名空间（默认为 default）
-                let namespace = obj.metadata.namespace.as_deref().unwrap_or("default");
-
-                // 创建对应的 API 资源
-                let api_resource = ApiResource::from_gvk(&gvk);
-                // 创建对应的 API 接口
-                let api: Api<DynamicObject> =
-                    Api::namespaced_with(client.clone(), namespace, &api_resource);
+                let registry = ClusterRegistry::load().await?;
+                let adapter = registry.resolve(cluster.as_deref())?;
+                let client = adapter.client().await?;
+                let default_namespace = adapter.default_namespace();
+
+                // 单个文档解析失败（比如多余的 `---` 产生的空文档）不能拖垮整份多资源
+                // 清单——这里逐个文档 parse 并把失败记成一条摘要，而不是用 `?` 提前
+                // return 掉还没来得及 apply 的其余文档。
+                let mut objects = Vec::new();
+                let mut summaries = Vec::new();
+                for (index, doc) in serde_yaml::Deserializer::from_str(&yaml_str_clone).enumerate()
+                {
+                    match DynamicObject::deserialize(doc) {
+                        Ok(obj) => objects.push(obj),
+                        Err(err) => summaries.push(format!("文档 #{}: 解析失败: {}", index + 1, err)),
+                    }
+                }
 
-                // 创建 Kubernetes 资源
-                let pp = PostParams::default();
-                api.create(&pp, &obj).await?;
-                // let res = format!(
-                //     " {}: {} in namespace {}",
-                //     kind,
-                //     obj.name_any(),
-                //     namespace
-                // );
-                let res = "部署完成".to_string();
-                println!("{}", res);
+                // Namespace/CRD 往往是其余对象的前置依赖，先应用它们再应用剩下的资源，
+                // 这样一份有序的多资源清单可以在一次调用里整体部署成功。
+                objects.sort_by_key(|obj| match kind_of(obj).as_deref() {
+                    Some("Namespace") | Some("CustomResourceDefinition") => 0,
+                    _ => 1,
+                });
+
+                for obj in &objects {
+                    let summary =
+                        match apply_single_document(&client, default_namespace, obj).await {
+                            Ok(ok) => ok,
+                            Err(err) => format!("{}: 部署失败: {}", describe(obj, default_namespace), err),
+                        };
+                    summaries.push(summary);
+                }
 
-                Ok(res)
+                Ok(summaries.join("\n"))
             });
             tx.send(result).unwrap();
         });
@@ -253,6 +176,43 @@ impl ApplyYamlToK8s {
     }
 }
 
+fn kind_of(obj: &DynamicObject) -> Option<String> {
+    obj.types.as_ref().map(|t| t.kind.clone())
+}
+
+fn describe(obj: &DynamicObject, default_namespace: &str) -> String {
+    let kind = kind_of(obj).unwrap_or_else(|| "未知类型".to_string());
+    let namespace = obj.metadata.namespace.as_deref().unwrap_or(default_namespace);
+    format!("{} {}/{}", kind, namespace, obj.name_any())
+}
+
+async fn apply_single_document(
+    client: &Client,
+    default_namespace: &str,
+    obj: &DynamicObject,
+) -> Result<String, ApplyError> {
+    let api_version = obj
+        .types
+        .as_ref()
+        .map(|t| t.api_version.clone())
+        .ok_or_else(|| anyhow::anyhow!("API版本信息缺失"))?;
+    let kind = kind_of(obj).ok_or_else(|| anyhow::anyhow!("类型信息缺失"))?;
+
+    // 解析 API 版本为 (group, version)；核心资源（如 v1/Pod）没有 group，按约定用空字符串。
+    let (group, version) = parse_api_version(api_version.as_str());
+    let group = group.unwrap_or("");
+    // 获取命名空间，缺省为该集群 adapter 规范化后的默认命名空间。
+    let namespace = obj.metadata.namespace.as_deref().unwrap_or(default_namespace);
+
+    // 通过 API discovery 解析该 GVK 的 ApiResource 与作用域，集群级资源（Namespace、
+    // ClusterRole、PersistentVolume 等）不再被强行塞进 namespaced API 而 panic。
+    let (api_resource, scope) = discovery::resolve(client, group, version, kind.as_str()).await?;
+    let api: Api<DynamicObject> = discovery::api_for(client.clone(), namespace, &api_resource, &scope);
+
+    apply_object(&api, obj).await?;
+    Ok(format!("{}: 部署完成", describe(obj, default_namespace)))
+}
+
 fn parse_api_version(api_version: &str) -> (Option<&str>, &str) {
     api_version
         .split_once('/')
@@ -0,0 +1,105 @@
+use kube::{
+    api::{Api, DynamicObject, Patch, PatchParams, PostParams},
+    ResourceExt,
+};
+use serde_json::json;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApplyError {
+    #[error("Kube error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("Serde error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// kubectl 写入的"最近一次应用的配置"注解，用于三路合并时计算哪些字段被用户删除了。
+pub const LAST_APPLIED_ANNOTATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// 实现 `kubectl apply` 语义：优先走 server-side apply，老版本集群不支持 SSA 时
+/// 回退到「先读后三路合并」的 strategic merge patch 路径，使重复执行部署幂等。
+/// 这是 apply 工具和 intent controller 共用的核心路径。
+pub async fn apply_object(
+    api: &Api<DynamicObject>,
+    obj: &DynamicObject,
+) -> Result<DynamicObject, ApplyError> {
+    let name = obj.name_any();
+    let ssa_params = PatchParams::apply("k8s-ops").force();
+    match api.patch(&name, &ssa_params, &Patch::Apply(obj)).await {
+        Ok(applied) => Ok(applied),
+        Err(kube::Error::Api(err)) if is_ssa_unsupported(&err) => {
+            apply_via_strategic_merge(api, &name, obj).await
+        }
+        // 只有「服务端不支持 SSA」才回退到三路合并；其他错误（校验失败、鉴权不足等）
+        // 原样向上抛出，不能被三路合并路径大概率同样失败的报错给掩盖掉。
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// 判断一次 SSA patch 失败是不是因为服务端根本不支持 server-side apply：
+/// 405（不认识 PATCH + `application/apply-patch+yaml`）或 415（拒绝这个 content-type）
+/// 是老版本 API server 的典型表现，其余状态码（如校验/鉴权失败）不应该被吞掉。
+fn is_ssa_unsupported(err: &kube::error::ErrorResponse) -> bool {
+    matches!(err.code, 405 | 415)
+}
+
+async fn apply_via_strategic_merge(
+    api: &Api<DynamicObject>,
+    name: &str,
+    obj: &DynamicObject,
+) -> Result<DynamicObject, ApplyError> {
+    match api.get_opt(name).await? {
+        None => {
+            let mut to_create = obj.clone();
+            let last_applied = serde_json::to_string(obj)?;
+            to_create
+                .annotations_mut()
+                .insert(LAST_APPLIED_ANNOTATION.to_string(), last_applied);
+            Ok(api.create(&PostParams::default(), &to_create).await?)
+        }
+        Some(existing) => {
+            let last_applied: serde_json::Value = existing
+                .annotations()
+                .get(LAST_APPLIED_ANNOTATION)
+                .map(|s| serde_json::from_str(s))
+                .transpose()?
+                .unwrap_or_else(|| json!({}));
+            let new_applied = serde_json::to_value(obj)?;
+            let mut patch = three_way_merge_patch(&last_applied, &new_applied);
+            patch["metadata"]["annotations"][LAST_APPLIED_ANNOTATION] =
+                json!(serde_json::to_string(&new_applied)?);
+            Ok(api
+                .patch(name, &PatchParams::default(), &Patch::Strategic(patch))
+                .await?)
+        }
+    }
+}
+
+/// 计算三路合并补丁：以新清单为准，但递归地把「上一次应用中存在、新清单里已被删除」
+/// 的字段置为 `null`，这样 strategic merge patch 会在服务端把它们一并删掉，而不是
+/// 残留旧值——包括嵌套对象里被删掉的字段（如 `spec.template.metadata.labels` 里
+/// 去掉的某个 label）。数组按 strategic merge 自身的合并键（如 containers 的
+/// `name`）在服务端合并，这里不逐元素 diff，直接整体替换为新值。
+fn three_way_merge_patch(
+    last_applied: &serde_json::Value,
+    new_applied: &serde_json::Value,
+) -> serde_json::Value {
+    match (last_applied.as_object(), new_applied.as_object()) {
+        (Some(last_map), Some(new_map)) => {
+            let mut patch = serde_json::Map::new();
+            for (key, new_value) in new_map {
+                let merged = match last_map.get(key) {
+                    Some(last_value) => three_way_merge_patch(last_value, new_value),
+                    None => new_value.clone(),
+                };
+                patch.insert(key.clone(), merged);
+            }
+            for key in last_map.keys() {
+                if !new_map.contains_key(key) {
+                    patch.insert(key.clone(), serde_json::Value::Null);
+                }
+            }
+            serde_json::Value::Object(patch)
+        }
+        _ => new_applied.clone(),
+    }
+}
@@ -0,0 +1,11 @@
+use k8s_ops::controller;
+use kube::Client;
+
+/// `NaturalLanguageIntent` reconcile 控制器的独立入口：watch 并持续协调这些对象，
+/// 使声明式部署保持自愈，而不是只在 agent 调用 `apply_yaml_to_k8s` 的那一瞬间生效。
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let client = Client::try_default().await?;
+    controller::run(client).await;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use std::{collections::HashMap, env, path::Path};
+
+use kube::{
+    config::{KubeConfigOptions, Kubeconfig},
+    Client, Config,
+};
+
+const IN_CLUSTER_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClusterError {
+    #[error("Kube error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("Kubeconfig 读取失败: {0}")]
+    Kubeconfig(#[from] kube::config::KubeconfigError),
+    #[error("Kubeconfig 解析失败: {0}")]
+    InferConfig(#[from] kube::config::InferConfigError),
+    #[error("未知集群: {0}")]
+    UnknownCluster(String),
+}
+
+/// 薄适配层：给定一个逻辑集群名，产出一个配置好的 `kube::Client`，并规范化该
+/// 集群的默认命名空间，这样上层工具不需要关心自己到底在跟哪个 vendor/context 打交道。
+#[async_trait::async_trait]
+pub trait ClusterAdapter: Send + Sync {
+    async fn client(&self) -> Result<Client, ClusterError>;
+    fn default_namespace(&self) -> &str;
+}
+
+struct KubeconfigContextAdapter {
+    context: String,
+    default_namespace: String,
+}
+
+#[async_trait::async_trait]
+impl ClusterAdapter for KubeconfigContextAdapter {
+    async fn client(&self) -> Result<Client, ClusterError> {
+        let kubeconfig = Kubeconfig::read()?;
+        let options = KubeConfigOptions {
+            context: Some(self.context.clone()),
+            ..Default::default()
+        };
+        let config = Config::from_custom_kubeconfig(kubeconfig, &options).await?;
+        Ok(Client::try_from(config)?)
+    }
+
+    fn default_namespace(&self) -> &str {
+        &self.default_namespace
+    }
+}
+
+struct InClusterAdapter {
+    default_namespace: String,
+}
+
+#[async_trait::async_trait]
+impl ClusterAdapter for InClusterAdapter {
+    async fn client(&self) -> Result<Client, ClusterError> {
+        let config = Config::incluster()?;
+        Ok(Client::try_from(config)?)
+    }
+
+    fn default_namespace(&self) -> &str {
+        &self.default_namespace
+    }
+}
+
+/// 多集群/多 context 的注册表：按名字加载本地 kubeconfig 里的每个 context
+/// （以及运行在集群内部时的 in-cluster config），让"部署到 staging 集群"这样
+/// 的提示可以解析到正确的 `Client`，而不是永远只能碰到 `Client::try_default()`
+/// 选中的那一个集群。
+pub struct ClusterRegistry {
+    adapters: HashMap<String, Box<dyn ClusterAdapter>>,
+    default_cluster: String,
+}
+
+impl ClusterRegistry {
+    /// 加载本地 kubeconfig 的所有 context；若检测到
+    /// `KUBERNETES_SERVICE_HOST`/`KUBERNETES_SERVICE_PORT` 和 service account
+    /// token 文件，额外注册一个名为 `"in-cluster"` 的 adapter。
+    pub async fn load() -> Result<Self, ClusterError> {
+        let mut adapters: HashMap<String, Box<dyn ClusterAdapter>> = HashMap::new();
+        let mut default_cluster: Option<String> = None;
+
+        if let Ok(kubeconfig) = Kubeconfig::read() {
+            for context in &kubeconfig.contexts {
+                let namespace = context
+                    .context
+                    .as_ref()
+                    .and_then(|c| c.namespace.clone())
+                    .unwrap_or_else(|| "default".to_string());
+                adapters.insert(
+                    context.name.clone(),
+                    Box::new(KubeconfigContextAdapter {
+                        context: context.name.clone(),
+                        default_namespace: namespace,
+                    }),
+                );
+            }
+            default_cluster = kubeconfig.current_context.clone();
+        }
+
+        if in_cluster_config_available() {
+            adapters.insert(
+                "in-cluster".to_string(),
+                Box::new(InClusterAdapter {
+                    default_namespace: "default".to_string(),
+                }),
+            );
+            default_cluster.get_or_insert_with(|| "in-cluster".to_string());
+        }
+
+        let default_cluster = default_cluster.unwrap_or_else(|| "in-cluster".to_string());
+        Ok(Self {
+            adapters,
+            default_cluster,
+        })
+    }
+
+    /// 解析一个逻辑集群名到对应的 adapter；`None` 表示使用默认集群（当前
+    /// kubeconfig context，或运行在集群内部时的 in-cluster config）。
+    pub fn resolve(&self, cluster: Option<&str>) -> Result<&dyn ClusterAdapter, ClusterError> {
+        let name = cluster.unwrap_or(self.default_cluster.as_str());
+        self.adapters
+            .get(name)
+            .map(|adapter| adapter.as_ref())
+            .ok_or_else(|| ClusterError::UnknownCluster(name.to_string()))
+    }
+}
+
+fn in_cluster_config_available() -> bool {
+    env::var("KUBERNETES_SERVICE_HOST").is_ok()
+        && env::var("KUBERNETES_SERVICE_PORT").is_ok()
+        && Path::new(IN_CLUSTER_TOKEN_PATH).exists()
+}
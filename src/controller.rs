@@ -0,0 +1,229 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, OwnerReference};
+use kube::{
+    api::{Api, DynamicObject, Patch, PatchParams},
+    core::Time,
+    discovery::Scope,
+    runtime::{controller::Action, Controller},
+    Client, Resource, ResourceExt,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    apply::{self, apply_object},
+    cluster::{self, ClusterRegistry},
+    discovery,
+    intent::NaturalLanguageIntent,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReconcileError {
+    #[error("Kube error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("Discovery error: {0}")]
+    Discovery(#[from] discovery::DiscoveryError),
+    #[error("Cluster error: {0}")]
+    Cluster(#[from] cluster::ClusterError),
+    #[error("Apply error: {0}")]
+    Apply(#[from] apply::ApplyError),
+    #[error("Serde yaml error: {0}")]
+    SerdeYaml(#[from] serde_yaml::Error),
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] anyhow::Error),
+}
+
+struct Context {
+    client: Client,
+}
+
+const SUCCESS_REQUEUE: Duration = Duration::from_secs(300);
+const FAILURE_REQUEUE: Duration = Duration::from_secs(30);
+
+/// 启动 `NaturalLanguageIntent` 的 reconcile 控制器：informer 持续 watch 这些对象，
+/// 每次协调都重新 apply `spec.manifests` 里的对象（复用幂等的 server-side-apply
+/// 路径）。同集群、namespaced 且位于 intent 自身命名空间的子资源会把 owner reference 指回 intent 以便随 intent
+/// 一起被垃圾回收；跨集群（`target_cluster` 指向别的集群）或集群级的子资源不挂
+/// owner，因为 owner UID 跨集群没有意义、集群级对象也不能挂 namespaced owner。
+/// 协调结果写回 `status.conditions`；apply 失败时在固定的 `FAILURE_REQUEUE`（30 秒）
+/// 后重新入队而不是放弃，重试间隔不随连续失败次数增长。
+///
+/// 子资源的具体 kind 由 LLM 生成的 `spec.manifests` 决定，协调前并不知道是哪些
+/// GVK，因此这里没有接 `.owns()`/`.watches()` 去对子资源做事件驱动的 watch——子
+/// 资源层面的 drift 只能靠 `SUCCESS_REQUEUE` 周期性重新协调来纠正，不是实时的。
+/// 如果以后收窄到一组已知的 kind，可以为它们接上 `.owns()` 做到真正的事件驱动。
+pub async fn run(client: Client) {
+    let intents: Api<NaturalLanguageIntent> = Api::all(client.clone());
+    let context = Arc::new(Context { client });
+
+    Controller::new(intents, Default::default())
+        .run(reconcile, error_policy, context)
+        .for_each(|res| async move {
+            match res {
+                Ok((obj, _)) => println!("reconciled {}", obj.name),
+                Err(err) => eprintln!("reconcile failed: {err}"),
+            }
+        })
+        .await;
+}
+
+async fn reconcile(
+    intent: Arc<NaturalLanguageIntent>,
+    ctx: Arc<Context>,
+) -> Result<Action, ReconcileError> {
+    let namespace = intent.spec.target_namespace.as_str();
+    let registry = ClusterRegistry::load().await?;
+    let adapter = registry.resolve(intent.spec.target_cluster.as_deref())?;
+    let target_client = adapter.client().await?;
+
+    // owner reference 的 UID 只在它所属的那个集群里有意义；跨集群的 intent（显式
+    // 指定了 `target_cluster`）把子资源建在别的集群上，那边的 GC 根本查不到这个
+    // UID，会把刚建好的子资源当成孤儿立刻删掉——所以只有同集群部署才挂 owner。
+    let same_cluster = intent.spec.target_cluster.is_none();
+    let owner_ref = intent
+        .controller_owner_ref(&())
+        .expect("NaturalLanguageIntent 缺少 uid，无法构造 owner reference");
+    let owner_namespace = intent.namespace();
+
+    let mut applied = Vec::new();
+    let mut failed = Vec::new();
+    for doc in serde_yaml::Deserializer::from_str(&intent.spec.manifests) {
+        let mut child: DynamicObject = DynamicObject::deserialize(doc)?;
+        match apply_child(
+            &target_client,
+            &mut child,
+            namespace,
+            same_cluster
+                .then_some(&owner_ref)
+                .zip(owner_namespace.as_deref()),
+        )
+        .await
+        {
+            Ok(()) => applied.push(describe(&child)),
+            Err(err) => failed.push(format!("{}: {err}", describe(&child))),
+        }
+    }
+
+    write_status(&ctx.client, &intent, &applied, &failed).await?;
+
+    if failed.is_empty() {
+        Ok(Action::requeue(SUCCESS_REQUEUE))
+    } else {
+        Ok(Action::requeue(FAILURE_REQUEUE))
+    }
+}
+
+fn error_policy(
+    _intent: Arc<NaturalLanguageIntent>,
+    _err: &ReconcileError,
+    _ctx: Arc<Context>,
+) -> Action {
+    Action::requeue(FAILURE_REQUEUE)
+}
+
+/// 解析子资源的 scope 后再 apply：`Namespace`/`ClusterRole`/`PersistentVolume`
+/// 这类集群级 kind 不能带 namespace 或 namespaced owner reference（apiserver 会拒绝
+/// 带 namespace 的集群级对象；GC 则会把 owner 指向不存在的 namespaced 对象的子资源
+/// 当孤儿删掉），所以要先 discovery 出 scope，再决定是否注入 namespace/owner。
+async fn apply_child(
+    client: &Client,
+    obj: &mut DynamicObject,
+    target_namespace: &str,
+    owner: Option<(&OwnerReference, &str)>,
+) -> Result<(), ReconcileError> {
+    let api_version = obj
+        .types
+        .as_ref()
+        .map(|t| t.api_version.clone())
+        .ok_or_else(|| anyhow::anyhow!("API版本信息缺失"))?;
+    let kind = obj
+        .types
+        .as_ref()
+        .map(|t| t.kind.clone())
+        .ok_or_else(|| anyhow::anyhow!("类型信息缺失"))?;
+    let (group, version) = api_version
+        .split_once('/')
+        .map_or(("", api_version.as_str()), |(g, v)| (g, v));
+
+    let (api_resource, scope) = discovery::resolve(client, group, version, &kind).await?;
+
+    match scope {
+        Scope::Namespaced => {
+            if obj.metadata.namespace.is_none() {
+                obj.metadata.namespace = Some(target_namespace.to_string());
+            }
+            // 只给落在 intent 自己命名空间里的 namespaced 子资源挂 owner——挂到别的
+            // 命名空间一样会被当成跨命名空间引用，同样不会被 apiserver 接受。
+            if let Some((owner_ref, owner_namespace)) = owner {
+                if obj.metadata.namespace.as_deref() == Some(owner_namespace) {
+                    obj.metadata
+                        .owner_references
+                        .get_or_insert_with(Vec::new)
+                        .push(owner_ref.clone());
+                }
+            }
+        }
+        Scope::Cluster => {}
+    }
+
+    let namespace = obj.metadata.namespace.as_deref().unwrap_or("default");
+    let api: Api<DynamicObject> =
+        discovery::api_for(client.clone(), namespace, &api_resource, &scope);
+    apply_object(&api, obj).await?;
+    Ok(())
+}
+
+fn describe(obj: &DynamicObject) -> String {
+    let kind = obj.types.as_ref().map(|t| t.kind.as_str()).unwrap_or("?");
+    let namespace = obj.metadata.namespace.as_deref().unwrap_or("-");
+    format!("{} {}/{}", kind, namespace, obj.name_any())
+}
+
+/// 把本轮协调的结果（哪些子资源 apply 成功/失败）作为一个 `Ready` condition
+/// 写回 intent 的 status 子资源，供用户 `kubectl describe` 时查看。
+async fn write_status(
+    client: &Client,
+    intent: &NaturalLanguageIntent,
+    applied: &[String],
+    failed: &[String],
+) -> Result<(), ReconcileError> {
+    let ready = failed.is_empty();
+    let condition = Condition {
+        type_: "Ready".to_string(),
+        status: if ready { "True" } else { "False" }.to_string(),
+        reason: if ready {
+            "AllResourcesApplied"
+        } else {
+            "ApplyFailed"
+        }
+        .to_string(),
+        message: if ready {
+            format!("applied: {}", applied.join(", "))
+        } else {
+            format!("applied: {}; failed: {}", applied.join(", "), failed.join("; "))
+        },
+        observed_generation: intent.metadata.generation,
+        last_transition_time: Time(chrono::Utc::now()),
+    };
+
+    let intents: Api<NaturalLanguageIntent> = Api::namespaced(
+        client.clone(),
+        intent.namespace().as_deref().unwrap_or("default"),
+    );
+    let patch = json!({
+        "status": {
+            "conditions": [condition],
+            "observedGeneration": intent.metadata.generation,
+        }
+    });
+    intents
+        .patch_status(
+            &intent.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(patch),
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use k8s_openapi::{
+    api::{apps::v1::ReplicaSet, core::v1::Pod},
+    apimachinery::pkg::apis::meta::v1::OwnerReference,
+};
+use k8s_openapi::api::core::v1::Event;
+use kube::{
+    api::{Api, LogParams},
+    Client, ResourceExt,
+};
+
+/// 同一个 involved object 在这个窗口内重复出现的 Warning 事件会被去重，
+/// 避免一个反复 CrashLoopBackOff 的 Pod 把同一段诊断反复喂给模型。
+const DEBOUNCE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 收集、去重 Pod 相关 Warning 事件的诊断上下文，供 `MultiTurnAgent` 做根因分析。
+pub struct EventDiagnostics {
+    client: Client,
+    last_seen: HashMap<String, Instant>,
+}
+
+impl EventDiagnostics {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// 同一 involved object（`namespace/name`）在 `DEBOUNCE_WINDOW` 内只处理一次，
+    /// 返回 `true` 表示这次事件应该继续处理。顺带把窗口之外的旧条目清掉——watcher
+    /// 长期运行，involved object 的集合（尤其是被删除重建的 Pod）会不断变化，
+    /// 不清理的话 `last_seen` 会随进程生命周期无限增长。
+    pub fn should_process(&mut self, involved_object_key: &str) -> bool {
+        let now = Instant::now();
+        self.last_seen
+            .retain(|_, seen| now.duration_since(*seen) < DEBOUNCE_WINDOW);
+
+        let should_process = !self.last_seen.contains_key(involved_object_key);
+        if should_process {
+            self.last_seen
+                .insert(involved_object_key.to_string(), now);
+        }
+        should_process
+    }
+
+    /// 收集一条涉及 Pod 的 Warning 事件的完整上下文：owner chain（Pod -> ReplicaSet
+    /// -> Deployment）、最近的 Pod 日志，以及事件本身的 reason/message，拼成一段
+    /// 可以直接喂给 LLM 的文本。
+    pub async fn gather_context(&self, event: &Event) -> anyhow::Result<String> {
+        let namespace = event
+            .involved_object
+            .namespace
+            .clone()
+            .unwrap_or_else(|| "default".to_string());
+        let pod_name = event
+            .involved_object
+            .name
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("event 缺少 involved_object.name"))?;
+
+        let owner_chain = self.owner_chain(&namespace, &pod_name).await;
+        let logs = self.recent_logs(&namespace, &pod_name).await;
+
+        Ok(format!(
+            "Owner chain: {}\nEvent reason: {}\nEvent message: {}\nRecent logs:\n{}",
+            owner_chain,
+            event.reason.as_deref().unwrap_or("-"),
+            event.message.as_deref().unwrap_or("-"),
+            logs,
+        ))
+    }
+
+    async fn owner_chain(&self, namespace: &str, pod_name: &str) -> String {
+        let mut chain = vec![format!("Pod/{pod_name}")];
+
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let Ok(pod) = pods.get(pod_name).await else {
+            return chain.join(" -> ");
+        };
+        let Some(rs_name) = owner_name(pod.owner_references(), "ReplicaSet") else {
+            return chain.join(" -> ");
+        };
+        chain.push(format!("ReplicaSet/{rs_name}"));
+
+        let replica_sets: Api<ReplicaSet> = Api::namespaced(self.client.clone(), namespace);
+        let Ok(rs) = replica_sets.get(&rs_name).await else {
+            return chain.join(" -> ");
+        };
+        if let Some(deploy_name) = owner_name(rs.owner_references(), "Deployment") {
+            chain.push(format!("Deployment/{deploy_name}"));
+        }
+
+        chain.join(" -> ")
+    }
+
+    async fn recent_logs(&self, namespace: &str, pod_name: &str) -> String {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let lp = LogParams {
+            tail_lines: Some(50),
+            ..Default::default()
+        };
+        pods.logs(pod_name, &lp)
+            .await
+            .unwrap_or_else(|err| format!("(无法获取日志: {err})"))
+    }
+}
+
+fn owner_name(owners: &[OwnerReference], kind: &str) -> Option<String> {
+    owners
+        .iter()
+        .find(|owner| owner.kind == kind)
+        .map(|owner| owner.name.clone())
+}
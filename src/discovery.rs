@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use kube::{
+    api::DynamicObject,
+    discovery::{ApiResource, Discovery, Scope},
+    Api, Client,
+};
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
+
+/// (集群地址, group, version, kind) -> 已解析的 `ApiResource` 及其作用域，避免同一
+/// 进程内重复 apply/query 时反复触发 API discovery。进程现在通过 `ClusterRegistry`
+/// 同时对接多个集群，缓存必须按集群区分，否则在集群 A 上解析到的 CRD 会被错误地
+/// 复用到没有这个 CRD 的集群 B 上，query/apply 会静默地查不到或 404，而不是正确地
+/// 报 "kind not found"。
+type CacheKey = (String, String, String, String);
+
+static RESOURCE_CACHE: Lazy<Mutex<HashMap<CacheKey, (ApiResource, Scope)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// (集群地址, kind) -> 已解析的 `ApiResource`/作用域，供只知道 kind（不知道具体
+/// group/version）的调用方（如查询工具）复用，同样按集群区分。
+static KIND_CACHE: Lazy<Mutex<HashMap<(String, String), (ApiResource, Scope)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 缓存键里用来区分集群的部分：集群 API server 的地址对同一个集群在进程生命周期内
+/// 是稳定的，不同 `ClusterAdapter`（不同 kubeconfig context/in-cluster）解析出的
+/// `Client` 地址也各不相同，适合直接拿来当缓存的集群维度。
+fn cluster_key(client: &Client) -> String {
+    client.cluster_url().to_string()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiscoveryError {
+    #[error("Kube error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("resource {group}/{version} Kind={kind} 在 API discovery 中未找到")]
+    NotFound {
+        group: String,
+        version: String,
+        kind: String,
+    },
+    #[error("Kind={kind} 在 API discovery 中未找到")]
+    KindNotFound { kind: String },
+}
+
+/// 通过 API discovery 解析给定 GVK 对应的 `ApiResource` 与作用域（集群级/命名空间级）。
+/// 核心资源（如 `Pod`、`Namespace`）的 group 传空字符串 `""`。结果按 (集群, GVK) 缓存。
+pub async fn resolve(
+    client: &Client,
+    group: &str,
+    version: &str,
+    kind: &str,
+) -> Result<(ApiResource, Scope), DiscoveryError> {
+    let key = (
+        cluster_key(client),
+        group.to_string(),
+        version.to_string(),
+        kind.to_string(),
+    );
+    if let Some(entry) = RESOURCE_CACHE.lock().await.get(&key) {
+        return Ok(entry.clone());
+    }
+
+    let discovery = Discovery::new(client.clone()).run().await?;
+    for api_group in discovery.groups() {
+        if api_group.name() != group {
+            continue;
+        }
+        for (resource, capabilities) in api_group.versioned_resources(version) {
+            if resource.kind == kind {
+                let entry = (resource, capabilities.scope.clone());
+                RESOURCE_CACHE.lock().await.insert(key, entry.clone());
+                return Ok(entry);
+            }
+        }
+    }
+
+    Err(DiscoveryError::NotFound {
+        group: group.to_string(),
+        version: version.to_string(),
+        kind: kind.to_string(),
+    })
+}
+
+/// 只按 kind 解析 `ApiResource`（不要求调用方知道 group/version）。同一个 kind 可能
+/// 同时存在于多个 group（如 `Event` 同时有核心 `v1` 和 `events.k8s.io`），这里收集
+/// 所有匹配并按 [`group_priority`] 排序——核心组（group == ""）优先，其次 `apps`，
+/// 其余按 discovery 枚举到的顺序——取优先级最高的一个，而不是随 discovery 返回顺序
+/// 任意选一个。用于查询类工具，结果按 (集群, kind) 缓存。
+pub async fn resolve_by_kind(
+    client: &Client,
+    kind: &str,
+) -> Result<(ApiResource, Scope), DiscoveryError> {
+    let key = (cluster_key(client), kind.to_string());
+    if let Some(entry) = KIND_CACHE.lock().await.get(&key) {
+        return Ok(entry.clone());
+    }
+
+    let discovery = Discovery::new(client.clone()).run().await?;
+    let mut candidates = Vec::new();
+    for api_group in discovery.groups() {
+        for (resource, capabilities) in api_group.recommended_resources() {
+            if resource.kind == kind {
+                candidates.push((resource, capabilities.scope.clone()));
+            }
+        }
+    }
+    candidates.sort_by_key(|(resource, _)| group_priority(&resource.group));
+
+    let entry = candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| DiscoveryError::KindNotFound {
+            kind: kind.to_string(),
+        })?;
+
+    KIND_CACHE.lock().await.insert(key, entry.clone());
+    Ok(entry)
+}
+
+/// 排序键：核心组（`""`）最优先，其次 `apps`，其余组保持 discovery 原有的相对顺序。
+fn group_priority(group: &str) -> u8 {
+    match group {
+        "" => 0,
+        "apps" => 1,
+        _ => 2,
+    }
+}
+
+/// 根据 discovery 解析出的作用域构造 `Api<DynamicObject>`：集群级资源用
+/// `Api::all_with`，命名空间级资源用 `Api::namespaced_with`，调用方不再需要关心
+/// 资源到底是不是命名空间级的。
+pub fn api_for(
+    client: Client,
+    namespace: &str,
+    resource: &ApiResource,
+    scope: &Scope,
+) -> Api<DynamicObject> {
+    match scope {
+        Scope::Cluster => Api::all_with(client, resource),
+        Scope::Namespaced => Api::namespaced_with(client, namespace, resource),
+    }
+}
@@ -0,0 +1,39 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 一次自然语言部署意图：保存用户原始 prompt、由 LLM 生成的清单，以及部署目标
+/// 命名空间/集群。`controller` 模块持续协调它和它拥有的子资源，让部署从
+/// 一次性的 `apply_yaml_to_k8s` 调用变成 level-triggered、可自愈的声明式工作流。
+#[derive(CustomResource, Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[kube(
+    group = "k8s-ops.dev",
+    version = "v1alpha1",
+    kind = "NaturalLanguageIntent",
+    plural = "naturallanguageintents",
+    shortname = "nli",
+    namespaced,
+    status = "NaturalLanguageIntentStatus",
+    derive = "PartialEq"
+)]
+#[serde(rename_all = "camelCase")]
+pub struct NaturalLanguageIntentSpec {
+    /// 用户最初的自然语言描述，仅作留痕，不参与协调。
+    pub prompt: String,
+    /// LLM 根据 prompt 生成的、`---` 分隔的多文档 YAML 清单。
+    pub manifests: String,
+    /// 清单中未显式指定 namespace 的对象所使用的默认命名空间。
+    pub target_namespace: String,
+    /// 目标集群名，对应 `ClusterRegistry` 里注册的 kubeconfig context；缺省使用当前 context。
+    pub target_cluster: Option<String>,
+}
+
+/// 字段名按 `camelCase` 序列化，使 `controller::write_status` 写入的 merge patch
+/// （如 `observedGeneration`）能和这个结构体的字段一一对应、正常往返反序列化。
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NaturalLanguageIntentStatus {
+    pub conditions: Vec<Condition>,
+    pub observed_generation: Option<i64>,
+}
@@ -0,0 +1,8 @@
+pub mod agent;
+pub mod apply;
+pub mod cluster;
+pub mod controller;
+pub mod diagnostics;
+pub mod discovery;
+pub mod intent;
+pub mod tools;
@@ -1,31 +1,81 @@
-use futures::TryStreamExt;
+use futures::StreamExt;
 use k8s_openapi::api::core::v1::Event;
+use k8s_ops::{agent::MultiTurnAgent, diagnostics::EventDiagnostics};
 use kube::{
     api::Api,
     runtime::{watcher, WatchStreamExt},
     Client,
 };
+use rig::providers::anthropic;
+
+/// 长驻的事件诊断子系统：监听 Warning 类型的 Pod 事件，收集 owner chain/日志等
+/// 上下文，交给 `MultiTurnAgent` 做根因分析并给出修复建议。watch 流用
+/// `default_backoff` 包一层负责重连退避，但 watcher 仍然会把单次 API 错误作为
+/// 一个 `Err` item 发给调用方——下面的循环把它当日志处理并 `continue`，而不是
+/// 用 `?` 把它向上抛出杀掉整个进程，这样瞬时错误才真正杀不死 watcher。
 #[tokio::main]
-async fn main() -> Result<(), watcher::Error> {
-    let client = Client::try_default().await.unwrap();
-    let pods: Api<Event> = Api::namespaced(client, "default");
+async fn main() -> anyhow::Result<()> {
+    let client = Client::try_default().await?;
+    let events: Api<Event> = Api::namespaced(client.clone(), "default");
+
+    let anthropic_client = anthropic::Client::from_env();
+    let diagnostic_agent = anthropic_client
+        .agent(anthropic::CLAUDE_3_5_SONNET)
+        .preamble(
+            "你是一个 K8s 故障诊断专家。根据提供的 owner chain、Pod 最近日志和事件的 \
+             reason/message，给出根因分析（root cause），并给出一个具体的修复建议，\
+             可以是修正后的 YAML 片段，也可以是等价的 kubectl 操作描述。",
+        )
+        .build();
+    let mut agent = MultiTurnAgent::new(diagnostic_agent);
+    let mut diagnostics = EventDiagnostics::new(client);
 
-    watcher(pods, watcher::Config::default())
+    let mut stream = watcher(events, watcher::Config::default())
+        .default_backoff()
         .applied_objects()
-        .try_for_each(|p| async move {
-            if p.type_ == Some("Warning".to_string())
-                && p.involved_object.kind == Some("Pod".to_string())
-            {
-                println!(
-                    "Warning: {:?} {:?} {:?} {:?}",
-                    p.involved_object.name.unwrap(),
-                    p.involved_object.namespace,
-                    p.reason.unwrap(),
-                    p.message.unwrap()
-                );
+        .boxed();
+
+    while let Some(item) = stream.next().await {
+        let event = match item {
+            Ok(event) => event,
+            Err(err) => {
+                eprintln!("watch 流出现错误，跳过本次事件并继续: {err}");
+                continue;
             }
-            Ok(())
-        })
-        .await?;
+        };
+
+        if event.type_.as_deref() != Some("Warning")
+            || event.involved_object.kind.as_deref() != Some("Pod")
+        {
+            continue;
+        }
+
+        let key = format!(
+            "{}/{}",
+            event
+                .involved_object
+                .namespace
+                .as_deref()
+                .unwrap_or("default"),
+            event.involved_object.name.as_deref().unwrap_or("-"),
+        );
+        if !diagnostics.should_process(&key) {
+            continue;
+        }
+
+        let context = match diagnostics.gather_context(&event).await {
+            Ok(context) => context,
+            Err(err) => {
+                eprintln!("收集诊断上下文失败 [{key}]: {err}");
+                continue;
+            }
+        };
+
+        match agent.multi_turn_prompt(context).await {
+            Ok(diagnosis) => println!("诊断结果 [{key}]:\n{diagnosis}\n"),
+            Err(err) => eprintln!("诊断失败 [{key}]: {err}"),
+        }
+    }
+
     Ok(())
 }
@@ -1,3 +1,8 @@
+use crate::{cluster::ClusterRegistry, discovery};
+use kube::{
+    api::{Api, DynamicObject, ListParams},
+    ResourceExt,
+};
 use rig::{completion::{Chat, Completion, Prompt}, providers::{self, deepseek::DEEPSEEK_CHAT}, tool::Tool};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -12,8 +17,141 @@ pub struct GenerateAndDeployResource;
 // }
 
 //查询k8s资源
-#[derive(Serialize, Deserialize)]
-struct QueryResource;
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QueryResource;
+
+#[derive(Deserialize, Clone, Serialize)]
+pub struct QueryArgs {
+    kind: String,
+    namespace: Option<String>,
+    name: Option<String>,
+    label_selector: Option<String>,
+    /// 目标集群名，对应 `ClusterRegistry` 里注册的 kubeconfig context 名（或
+    /// `"in-cluster"`）；缺省表示使用当前 kubeconfig context。
+    cluster: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("Kube error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("Discovery error: {0}")]
+    Discovery(#[from] discovery::DiscoveryError),
+    #[error("Cluster error: {0}")]
+    Cluster(#[from] crate::cluster::ClusterError),
+}
+
+impl Tool for QueryResource {
+    const NAME: &'static str = "query_resource";
+    type Error = QueryError;
+    type Args = QueryArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        serde_json::from_value(json!(
+            {
+                "name": Self::NAME,
+                "description": "查询 K8S 集群资源状态，可按 kind/namespace/name/标签选择器查询",
+                "parameters":
+                    {
+                        "type": "object",
+                        "properties":
+                            {
+                                "kind":
+                                    {
+                                        "type": "string",
+                                        "description": "资源类型，如 Deployment、Pod、Namespace"
+                                    },
+                                "namespace":
+                                    {
+                                        "type": "string",
+                                        "description": "命名空间，缺省为目标集群 context 的默认命名空间（未配置时为 default）；查询集群级资源时忽略"
+                                    },
+                                "name":
+                                    {
+                                        "type": "string",
+                                        "description": "资源名称，缺省表示列出该 kind 下的所有资源"
+                                    },
+                                "label_selector":
+                                    {
+                                        "type": "string",
+                                        "description": "标签选择器，如 app=nginx，用于批量查询"
+                                    },
+                                "cluster":
+                                    {
+                                        "type": "string",
+                                        "description": "目标集群名，对应 kubeconfig context；缺省使用当前 context"
+                                    },
+                            },
+                        "required": ["kind"]
+                    }
+            }
+        ))
+        .expect("查询工具定义生成失败")
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let registry = ClusterRegistry::load().await?;
+        let adapter = registry.resolve(args.cluster.as_deref())?;
+        let client = adapter.client().await?;
+        let namespace = args.namespace.as_deref().unwrap_or(adapter.default_namespace());
+
+        let (api_resource, scope) = discovery::resolve_by_kind(&client, &args.kind).await?;
+        let api: Api<DynamicObject> =
+            discovery::api_for(client, namespace, &api_resource, &scope);
+
+        match args.name.as_deref() {
+            Some(name) => match api.get_opt(name).await? {
+                Some(obj) => Ok(summarize(&obj)),
+                None => Ok(format!("{} {} 未找到", args.kind, name)),
+            },
+            None => {
+                let mut lp = ListParams::default();
+                if let Some(selector) = args.label_selector.as_deref() {
+                    lp = lp.labels(selector);
+                }
+                let list = api.list(&lp).await?;
+                if list.items.is_empty() {
+                    return Ok(format!("未找到任何 {} 资源", args.kind));
+                }
+                Ok(list.items.iter().map(summarize).collect::<Vec<_>>().join("\n"))
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        Self::NAME.to_string()
+    }
+}
+
+/// 从 `DynamicObject` 的 `status` 字段里摘取最常被关心的健康信息：
+/// phase（如 Running）、readyReplicas（工作负载常见字段）和各 condition 的 type=status。
+fn summarize(obj: &DynamicObject) -> String {
+    let name = obj.name_any();
+    let namespace = obj.metadata.namespace.as_deref().unwrap_or("-");
+    let status = &obj.data["status"];
+    let phase = status["phase"].as_str().unwrap_or("-");
+    let ready_replicas = status["readyReplicas"].as_u64();
+    let conditions = status["conditions"]
+        .as_array()
+        .map(|conditions| {
+            conditions
+                .iter()
+                .filter_map(|c| Some(format!("{}={}", c["type"].as_str()?, c["status"].as_str()?)))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .unwrap_or_default();
+
+    let mut summary = format!("{}/{} phase={}", namespace, name, phase);
+    if let Some(ready) = ready_replicas {
+        summary.push_str(&format!(" readyReplicas={}", ready));
+    }
+    if !conditions.is_empty() {
+        summary.push_str(&format!(" conditions=[{}]", conditions));
+    }
+    summary
+}
 
 #[derive(Debug, thiserror::Error)]
 #[error("error: {0}")]